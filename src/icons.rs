@@ -0,0 +1,221 @@
+//! Resolution of icon names against installed freedesktop icon themes
+//! (`index.theme`), as an alternative to the embedded iconsets baked into
+//! the binary via `ICON_DIR`.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use walkdir::WalkDir;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DirType {
+    Fixed,
+    Scalable,
+    Threshold,
+}
+
+struct IconDir {
+    path: String,
+    size: u32,
+    min_size: u32,
+    max_size: u32,
+    threshold: u32,
+    dir_type: DirType,
+}
+
+struct IconTheme {
+    base: PathBuf,
+    directories: Vec<IconDir>,
+    inherits: Vec<String>,
+}
+
+/// The standard locations a freedesktop-compliant desktop searches for icon
+/// themes, in priority order.
+fn search_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+
+    if let Ok(xdg) = std::env::var("XDG_DATA_DIRS") {
+        for dir in xdg.split(':').filter(|d| !d.is_empty()) {
+            paths.push(PathBuf::from(dir).join("icons"));
+        }
+    }
+    if let Ok(home) = std::env::var("HOME") {
+        paths.push(PathBuf::from(home).join(".local/share/icons"));
+    }
+    paths.push(PathBuf::from("/usr/share/icons"));
+
+    paths
+}
+
+fn find_theme_dir(theme: &str) -> Option<PathBuf> {
+    for base in search_paths() {
+        for entry in WalkDir::new(&base)
+            .max_depth(1)
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            if entry.file_type().is_dir()
+                && entry.file_name().to_str() == Some(theme)
+                && entry.path().join("index.theme").is_file()
+            {
+                return Some(entry.path().to_path_buf());
+            }
+        }
+    }
+
+    None
+}
+
+fn parse_index_theme(dir: &Path) -> Option<IconTheme> {
+    let contents = std::fs::read_to_string(dir.join("index.theme")).ok()?;
+
+    let mut section = String::new();
+    let mut top_level: HashMap<String, String> = HashMap::new();
+    let mut sections: HashMap<String, HashMap<String, String>> = HashMap::new();
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') && line.ends_with(']') {
+            section = line[1..line.len() - 1].to_string();
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let (key, value) = (key.trim().to_string(), value.trim().to_string());
+
+        if section == "Icon Theme" {
+            top_level.insert(key, value);
+        } else {
+            sections.entry(section.clone()).or_default().insert(key, value);
+        }
+    }
+
+    let directories_raw = top_level.get("Directories")?;
+    let inherits = top_level
+        .get("Inherits")
+        .map(|s| s.split(',').map(|s| s.trim().to_string()).collect())
+        .unwrap_or_default();
+
+    let directories = directories_raw
+        .split(',')
+        .map(|s| s.trim())
+        .filter_map(|dir_name| {
+            let props = sections.get(dir_name)?;
+            let size: u32 = props.get("Size").and_then(|s| s.parse().ok()).unwrap_or(48);
+            let dir_type = match props.get("Type").map(String::as_str) {
+                Some("Fixed") => DirType::Fixed,
+                Some("Scalable") => DirType::Scalable,
+                _ => DirType::Threshold,
+            };
+            let min_size = props.get("MinSize").and_then(|s| s.parse().ok()).unwrap_or(size);
+            let max_size = props.get("MaxSize").and_then(|s| s.parse().ok()).unwrap_or(size);
+            let threshold = props.get("Threshold").and_then(|s| s.parse().ok()).unwrap_or(2);
+
+            Some(IconDir {
+                path: dir_name.to_string(),
+                size,
+                min_size,
+                max_size,
+                threshold,
+                dir_type,
+            })
+        })
+        .collect();
+
+    Some(IconTheme {
+        base: dir.to_path_buf(),
+        directories,
+        inherits,
+    })
+}
+
+fn dir_matches_size(d: &IconDir, size: u32) -> bool {
+    match d.dir_type {
+        DirType::Fixed => d.size == size,
+        DirType::Scalable => size >= d.min_size && size <= d.max_size,
+        DirType::Threshold => {
+            size + d.threshold >= d.size && size <= d.size + d.threshold
+        }
+    }
+}
+
+thread_local! {
+    // `find_theme_dir` walks every configured icon path (e.g. the whole of
+    // `/usr/share/icons`) and `parse_index_theme` re-reads and re-parses
+    // `index.theme`; both are wasteful to repeat for every `FileItem` in a
+    // `generate` run. Cache the parsed theme (or its absence) per name for
+    // the lifetime of the process, since themes don't change mid-run.
+    static THEME_CACHE: RefCell<HashMap<String, Option<Rc<IconTheme>>>> = RefCell::new(HashMap::new());
+}
+
+/// Locate and parse `theme`'s `index.theme`, memoized so repeated lookups
+/// (across files, and across a theme's `Inherits` chain) don't re-walk the
+/// filesystem.
+fn themed(theme: &str) -> Option<Rc<IconTheme>> {
+    if let Some(cached) = THEME_CACHE.with(|c| c.borrow().get(theme).cloned()) {
+        return cached;
+    }
+
+    let parsed = find_theme_dir(theme)
+        .and_then(|dir| parse_index_theme(&dir))
+        .map(Rc::new);
+    THEME_CACHE.with(|c| c.borrow_mut().insert(theme.to_string(), parsed.clone()));
+    parsed
+}
+
+fn find_in_theme(
+    theme: &str,
+    icon_names: &[String],
+    size: u32,
+    visited: &mut Vec<String>,
+) -> Option<Vec<u8>> {
+    if visited.iter().any(|t| t == theme) {
+        return None;
+    }
+    visited.push(theme.to_string());
+
+    let parsed = themed(theme)?;
+
+    // Prefer a directory matching the requested size, falling back to
+    // whatever directories the theme declares.
+    let mut dirs: Vec<&IconDir> = parsed.directories.iter().collect();
+    dirs.sort_by_key(|d| if dir_matches_size(d, size) { 0 } else { 1 });
+
+    for name in icon_names {
+        for dir in &dirs {
+            for ext in ["svg", "png"] {
+                let candidate = parsed.base.join(&dir.path).join(format!("{}.{}", name, ext));
+                if let Ok(bytes) = std::fs::read(&candidate) {
+                    return Some(bytes);
+                }
+            }
+        }
+    }
+
+    for parent in parsed.inherits.iter() {
+        if let Some(bytes) = find_in_theme(parent, icon_names, size, visited) {
+            return Some(bytes);
+        }
+    }
+
+    None
+}
+
+/// Resolve the first of `icon_names` found in `theme` (following its
+/// `Inherits` chain), falling back to the `hicolor` base theme.
+pub fn resolve(icon_names: &[String], theme: &str, size: u32) -> Option<Vec<u8>> {
+    let mut visited = Vec::new();
+    find_in_theme(theme, icon_names, size, &mut visited).or_else(|| {
+        if theme == "hicolor" {
+            None
+        } else {
+            let mut visited = Vec::new();
+            find_in_theme("hicolor", icon_names, size, &mut visited)
+        }
+    })
+}
@@ -1,12 +1,23 @@
 use base64::prelude::*;
 use chrono::{DateTime, Local};
 use getopts::{Matches, Options};
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use include_dir::{include_dir, Dir};
 use serde::Serialize;
-use std::{collections::HashMap, env, fs::File, io::Write, path::MAIN_SEPARATOR_STR};
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    fs::File,
+    io::Write,
+    path::{PathBuf, MAIN_SEPARATOR_STR},
+};
 use tera::{Context, Tera};
 use walkdir::{DirEntry, WalkDir};
 
+mod icons;
+mod preview;
+mod serve;
+
 #[derive(Serialize)]
 struct Product {
     ig: Index,
@@ -17,9 +28,20 @@ struct Index {
     root: String,
     files: Vec<FileItem>,
     generator: Generator,
+    sort: Option<String>,
+    pager: Option<Pager>,
 }
 
 #[derive(Serialize)]
+struct Pager {
+    current: usize,
+    total_pages: usize,
+    page_size: usize,
+    prev: Option<String>,
+    next: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
 struct FileItem {
     path: String,
     name: String,
@@ -28,6 +50,13 @@ struct FileItem {
     mime: String,
     is_dir: bool,
     icon: String,
+    preview: Option<String>,
+    // Kept alongside the human-facing `size`/`modified` strings so sorting
+    // can compare the real values instead of re-parsing formatted text.
+    #[serde(skip)]
+    size_bytes: u64,
+    #[serde(skip)]
+    modified_at: DateTime<Local>,
 }
 
 #[derive(Serialize, Clone)]
@@ -37,6 +66,134 @@ struct Generator {
     url: String,
 }
 
+#[derive(Serialize)]
+struct FeedProduct {
+    feed: Feed,
+}
+
+#[derive(Serialize)]
+struct Feed {
+    kind: String,
+    root: String,
+    generator: Generator,
+    entries: Vec<FeedEntry>,
+}
+
+#[derive(Serialize)]
+struct FeedEntry {
+    title: String,
+    link: String,
+    updated: String,
+    mime: String,
+}
+
+#[derive(Serialize)]
+struct PreviewProduct {
+    preview: Preview,
+}
+
+#[derive(Serialize)]
+struct Preview {
+    name: String,
+    root: String,
+    html: String,
+    generator: Generator,
+}
+
+/// Resolved CLI options for a `generate` run, threaded through to the
+/// watch-and-serve loop unchanged so incremental rebuilds use the same
+/// settings as the initial pass.
+#[derive(Clone)]
+struct GenerateOptions {
+    theme: String,
+    name: String,
+    print: bool,
+    max_depth: usize,
+    root: String,
+    human: bool,
+    template: Option<String>,
+    iconset: String,
+    includes: Vec<String>,
+    excludes: Vec<String>,
+    sort: Option<(SortKey, SortDir)>,
+    group_dirs: bool,
+    feed: Option<FeedKind>,
+    feed_limit: Option<usize>,
+    page_size: Option<usize>,
+    previews: bool,
+    highlight_theme: String,
+    preview_limit: u64,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum FeedKind {
+    Atom,
+    Rss,
+}
+
+impl FeedKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            FeedKind::Atom => "atom",
+            FeedKind::Rss => "rss",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum SortKey {
+    Name,
+    Size,
+    Modified,
+    Type,
+}
+
+impl SortKey {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SortKey::Name => "name",
+            SortKey::Size => "size",
+            SortKey::Modified => "modified",
+            SortKey::Type => "type",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum SortDir {
+    Asc,
+    Desc,
+}
+
+impl SortDir {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SortDir::Asc => "asc",
+            SortDir::Desc => "desc",
+        }
+    }
+}
+
+/// Parse a `--sort KEY[:asc|desc]` argument, defaulting to ascending.
+fn parse_sort(spec: &str) -> Result<(SortKey, SortDir), Box<dyn std::error::Error>> {
+    let (key, dir) = spec.split_once(':').unwrap_or((spec, "asc"));
+
+    let key = match key {
+        "name" => SortKey::Name,
+        "size" => SortKey::Size,
+        "modified" => SortKey::Modified,
+        "type" => SortKey::Type,
+        other => return Err(format!("unknown --sort key '{}'", other).into()),
+    };
+    let dir = match dir {
+        "asc" => SortDir::Asc,
+        "desc" => SortDir::Desc,
+        other => return Err(format!("unknown --sort direction '{}'", other).into()),
+    };
+
+    Ok((key, dir))
+}
+
 static TEMPLATE_DIR: Dir = include_dir!("templates");
 static ICON_DIR: Dir = include_dir!("icons");
 
@@ -60,6 +217,61 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .optopt("r", "root", "Set base root dir.", "PATH")
         .optflag("", "human", "Make size human readable.")
         .optopt("", "iconset", "Choose iconset.", "ICON")
+        .optflag(
+            "",
+            "serve",
+            "Watch PATH for changes and serve the output over HTTP.",
+        )
+        .optopt("p", "port", "Port to serve on (with --serve).", "PORT")
+        .optmulti(
+            "",
+            "include",
+            "Only list entries matching GLOB (repeatable).",
+            "GLOB",
+        )
+        .optmulti(
+            "",
+            "exclude",
+            "Never list entries matching GLOB (repeatable, wins over --include).",
+            "GLOB",
+        )
+        .optopt(
+            "",
+            "sort",
+            "Sort entries by KEY[:asc|desc].",
+            "[name, size, modified, type]",
+        )
+        .optflag("", "group-dirs", "List directories before files.")
+        .optopt(
+            "",
+            "feed",
+            "Write a feed.xml of recently modified files alongside index.html.",
+            "atom|rss",
+        )
+        .optopt("", "feed-limit", "Cap feed entries to N (with --feed).", "N")
+        .optopt(
+            "",
+            "page-size",
+            "Split large directory listings into pages of N entries.",
+            "N",
+        )
+        .optflag(
+            "",
+            "previews",
+            "Generate syntax-highlighted preview pages for text files.",
+        )
+        .optopt(
+            "",
+            "highlight-theme",
+            "Syntect theme to use for --previews.",
+            "NAME",
+        )
+        .optopt(
+            "",
+            "preview-limit",
+            "Skip --previews for files larger than N bytes.",
+            "BYTES",
+        )
         .optflag("h", "help", "print this help menu");
 
     let matches = match opts.parse(&args[1..]) {
@@ -113,43 +325,191 @@ fn app(program: &str, matches: Matches, opts: Options) -> Result<(), Box<dyn std
         .unwrap_or(MAIN_SEPARATOR_STR.to_owned());
     let human = matches.opt_present("human");
     let iconset = matches.opt_str("iconset").unwrap_or("papirus".into());
+    let watch = matches.opt_present("serve");
+    let port = matches.opt_get_default("port", 8080u16)?;
+    let includes = matches.opt_strs("include");
+    let excludes = matches.opt_strs("exclude");
+    let sort = matches.opt_str("sort").map(|s| parse_sort(&s)).transpose()?;
+    let group_dirs = matches.opt_present("group-dirs");
+    let feed = matches
+        .opt_str("feed")
+        .map(|kind| {
+            Ok::<_, Box<dyn std::error::Error>>(match kind.as_str() {
+                "atom" => FeedKind::Atom,
+                "rss" => FeedKind::Rss,
+                other => return Err(format!("unknown --feed kind '{}'", other).into()),
+            })
+        })
+        .transpose()?;
+    let feed_limit = matches.opt_get::<usize>("feed-limit")?;
+    let page_size = matches.opt_get::<usize>("page-size")?;
+    let previews = matches.opt_present("previews");
+    let highlight_theme = matches
+        .opt_str("highlight-theme")
+        .unwrap_or("InspiredGitHub".into());
+    let preview_limit = matches.opt_get_default::<u64>("preview-limit", 1_048_576)?;
+
+    let depth = if no_recursive { 1 } else { depth };
+
+    let opts = GenerateOptions {
+        theme,
+        name,
+        print,
+        max_depth: depth,
+        root,
+        human,
+        template,
+        iconset,
+        includes,
+        excludes,
+        sort,
+        group_dirs,
+        feed,
+        feed_limit,
+        page_size,
+        previews,
+        highlight_theme,
+        preview_limit,
+    };
 
-    if no_recursive {
-        generate(
-            theme, &path, name, print, 1, root, human, &template, iconset,
-        )?;
-    } else {
-        generate(
-            theme, &path, name, print, depth, root, human, &template, iconset,
-        )?;
+    generate(&path, &opts)?;
+
+    if watch {
+        // `generate` already chdir'd into `path`, so the watcher and server
+        // below operate relative to the generated tree.
+        serve::watch_and_serve(&opts, port)?;
     }
+
     Ok(())
 }
 
-fn generate(
-    theme: String,
-    path: &str,
-    name: String,
-    if_print: bool,
-    max_depth: usize,
-    base: String,
-    human: bool,
-    template: &Option<String>,
-    iconset: String,
-) -> Result<(), Box<dyn std::error::Error>> {
+fn generate(path: &str, opts: &GenerateOptions) -> Result<(), Box<dyn std::error::Error>> {
     std::env::set_current_dir(path)?;
 
+    let map = collect_entries(opts)?;
+    let tera = build_tera(opts)?;
+
+    render_tree(&map, &tera, opts)?;
+
+    Ok(())
+}
+
+/// Build the `GlobSet` matchers for `--include`/`--exclude`, returning
+/// `None` for an empty include list (meaning "include everything").
+fn build_matchers(
+    includes: &[String],
+    excludes: &[String],
+) -> Result<(Option<GlobSet>, GlobSet), Box<dyn std::error::Error>> {
+    let build = |patterns: &[String]| -> Result<GlobSet, Box<dyn std::error::Error>> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(Glob::new(pattern)?);
+        }
+        Ok(builder.build()?)
+    };
+
+    let include_set = if includes.is_empty() {
+        None
+    } else {
+        Some(build(includes)?)
+    };
+
+    Ok((include_set, build(excludes)?))
+}
+
+/// Whether `e` is pruned by `--exclude`: either directly matched, or (for a
+/// directory) wholesale-excluded via a pattern like `dir/**` that only
+/// matches the directory's *contents*. `exclude_set` alone can't tell "this
+/// whole subtree is excluded" from "some of this directory's children are",
+/// so directories are additionally probed with a synthetic child path.
+fn is_excluded(e: &DirEntry, exclude_set: &GlobSet) -> bool {
+    let rel = e.path().strip_prefix(".").unwrap_or(e.path());
+    exclude_set.is_match(rel) || (e.file_type().is_dir() && exclude_set.is_match(rel.join("*")))
+}
+
+/// Directories (by path, e.g. `./sub`) that contain at least one
+/// non-excluded file matching `include_set`, anywhere in their subtree.
+/// Used so `--include` can keep WalkDir descending into (and listing) a
+/// directory with no directly-matching files of its own, while still
+/// pruning directories whose subtree has no match at all.
+fn dirs_with_match(
+    opts: &GenerateOptions,
+    exclude_set: &GlobSet,
+    include_set: &GlobSet,
+) -> Result<HashSet<PathBuf>, Box<dyn std::error::Error>> {
+    let mut dirs = HashSet::new();
+
+    for entry in WalkDir::new(".")
+        .max_depth(opts.max_depth)
+        .into_iter()
+        .filter_entry(|e| {
+            !e.file_name()
+                .to_str()
+                .is_some_and(|n| is_generated_output(n, opts))
+                && !is_excluded(e, exclude_set)
+        })
+    {
+        let entry = entry?;
+        let rel = entry.path().strip_prefix(".").unwrap_or(entry.path());
+        if entry.file_type().is_dir() || !include_set.is_match(rel) {
+            continue;
+        }
+
+        let mut ancestor = entry.path().parent();
+        while let Some(dir) = ancestor {
+            if !dirs.insert(dir.to_path_buf()) {
+                break; // already marked, so every further ancestor is too
+            }
+            ancestor = dir.parent();
+        }
+    }
+
+    Ok(dirs)
+}
+
+/// Walk `.` up to `opts.max_depth`, grouping entries by their parent
+/// directory. Every file `rindgen` itself generates for this run (the
+/// index pages, `feed.xml`, preview pages — see [`is_generated_output`])
+/// is always skipped so the generator never lists its own output;
+/// `--include` and `--exclude` globs further narrow the listing, with
+/// excludes always winning and excluded directories pruned entirely.
+fn collect_entries(
+    opts: &GenerateOptions,
+) -> Result<HashMap<String, Vec<DirEntry>>, Box<dyn std::error::Error>> {
+    let (include_set, exclude_set) = build_matchers(&opts.includes, &opts.excludes)?;
+    let dirs_with_match = include_set
+        .as_ref()
+        .map(|set| dirs_with_match(opts, &exclude_set, set))
+        .transpose()?;
     let mut map: HashMap<String, Vec<DirEntry>> = HashMap::new();
 
     for entry in WalkDir::new(".")
-        .max_depth(max_depth)
+        .max_depth(opts.max_depth)
         .sort_by_file_name()
         .into_iter()
         .filter_entry(|e| {
-            !matches!(
-                e.file_name().to_str(),
-                Some("index.html") | Some("images") | Some("favicon.ico")
-            )
+            if e.file_name()
+                .to_str()
+                .is_some_and(|n| is_generated_output(n, opts))
+            {
+                return false;
+            }
+
+            if is_excluded(e, &exclude_set) {
+                return false;
+            }
+
+            let rel = e.path().strip_prefix(".").unwrap_or(e.path());
+            match &include_set {
+                Some(set) => {
+                    e.depth() == 0
+                        || set.is_match(rel)
+                        || dirs_with_match
+                            .as_ref()
+                            .is_some_and(|d| d.contains(e.path()))
+                }
+                None => true,
+            }
         })
     {
         let entry = entry?;
@@ -164,14 +524,22 @@ fn generate(
         }
     }
 
-    let mut tera = match template {
+    Ok(map)
+}
+
+/// Load the layout/index templates for `theme` (or a user-supplied
+/// `template` directory) into a ready-to-render [`Tera`] instance.
+/// `feed.xml` is only registered when `opts.feed` is set, so themes that
+/// never generate feeds don't need to ship the template.
+fn build_tera(opts: &GenerateOptions) -> Result<Tera, Box<dyn std::error::Error>> {
+    let mut tera = match &opts.template {
         Some(t) => Tera::new(t)?,
         None => {
             let mut raw = Tera::default();
             raw.add_raw_template(
                 "layout.html",
                 TEMPLATE_DIR
-                    .get_file(theme.clone() + MAIN_SEPARATOR_STR + "layout.html")
+                    .get_file(opts.theme.clone() + MAIN_SEPARATOR_STR + "layout.html")
                     .unwrap()
                     .contents_utf8()
                     .unwrap(),
@@ -179,11 +547,31 @@ fn generate(
             raw.add_raw_template(
                 "index.html",
                 TEMPLATE_DIR
-                    .get_file(theme + MAIN_SEPARATOR_STR + "index.html")
+                    .get_file(opts.theme.clone() + MAIN_SEPARATOR_STR + "index.html")
                     .unwrap()
                     .contents_utf8()
                     .unwrap(),
             )?;
+            if opts.feed.is_some() {
+                raw.add_raw_template(
+                    "feed.xml",
+                    TEMPLATE_DIR
+                        .get_file(opts.theme.clone() + MAIN_SEPARATOR_STR + "feed.xml")
+                        .unwrap()
+                        .contents_utf8()
+                        .unwrap(),
+                )?;
+            }
+            if opts.previews {
+                raw.add_raw_template(
+                    "preview.html",
+                    TEMPLATE_DIR
+                        .get_file(opts.theme.clone() + MAIN_SEPARATOR_STR + "preview.html")
+                        .unwrap()
+                        .contents_utf8()
+                        .unwrap(),
+                )?;
+            }
 
             raw
         }
@@ -191,43 +579,109 @@ fn generate(
 
     tera.autoescape_on(vec![".html", ".htm"]);
 
+    Ok(tera)
+}
+
+fn render_tree(
+    map: &HashMap<String, Vec<DirEntry>>,
+    tera: &Tera,
+    opts: &GenerateOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let highlighter = opts.previews.then(preview::Highlighter::new);
+
     for (k, v) in map.iter() {
-        let mut files = Vec::new();
-
-        for f in v {
-            let mime = mime_guess::from_path(f.path())
-                .first_raw()
-                .unwrap_or("")
-                .to_string();
-            let is_dir = f.file_type().is_dir();
-            let modified: DateTime<Local> = f.metadata()?.modified()?.into();
-
-            let fi = FileItem {
-                path: f.path().to_str().unwrap().into(),
-                name: f.file_name().to_str().unwrap().into(),
-                size: if human {
-                    size_fmt(f.metadata()?.len())
-                } else {
-                    f.metadata()?.len().to_string()
-                },
-                modified: modified.format("%Y-%m-%d %H:%M:%S").to_string(),
-                mime: mime.clone(),
-                is_dir,
-                icon: get_icon_by_mime(mime.clone(), is_dir, iconset.clone()),
-            };
-            files.push(fi);
-        }
+        render_directory(k, v, tera, opts, highlighter.as_ref())?;
+    }
+
+    Ok(())
+}
+
+/// Render and write the `index.html` for a single directory `k`, given its
+/// already-collected entries `v`. `highlighter` is `Some` only when
+/// `--previews` is set, and is shared across every directory in the run.
+fn render_directory(
+    k: &str,
+    v: &[DirEntry],
+    tera: &Tera,
+    opts: &GenerateOptions,
+    highlighter: Option<&preview::Highlighter>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let root = opts.root.clone()
+        + k.strip_prefix('.')
+            .unwrap_or("")
+            .strip_prefix('/')
+            .unwrap_or("");
+
+    let mut files = Vec::new();
+
+    for f in v {
+        let mime = mime_guess::from_path(f.path())
+            .first_raw()
+            .unwrap_or("")
+            .to_string();
+        let is_dir = f.file_type().is_dir();
+        let modified: DateTime<Local> = f.metadata()?.modified()?.into();
+
+        let size_bytes = f.metadata()?.len();
+
+        let preview = highlighter
+            .filter(|_| !is_dir && size_bytes <= opts.preview_limit)
+            .filter(|_| preview::is_previewable(&mime, f.path()))
+            .and_then(|h| render_preview(k, f, &root, h, tera, opts));
+
+        let fi = FileItem {
+            path: f.path().to_str().unwrap().into(),
+            name: f.file_name().to_str().unwrap().into(),
+            size: if opts.human {
+                size_fmt(size_bytes)
+            } else {
+                size_bytes.to_string()
+            },
+            modified: modified.format("%Y-%m-%d %H:%M:%S").to_string(),
+            mime: mime.clone(),
+            is_dir,
+            icon: get_icon_by_mime(mime.clone(), is_dir, opts.iconset.clone()),
+            preview,
+            size_bytes,
+            modified_at: modified,
+        };
+        files.push(fi);
+    }
+
+    sort_files(&mut files, opts.sort, opts.group_dirs);
+
+    if opts.feed.is_some() {
+        render_feed(k, &root, &files, tera, opts)?;
+    }
+
+    let page_size = opts.page_size.filter(|s| *s > 0);
+    let pages: Vec<&[FileItem]> = match page_size {
+        Some(size) if !files.is_empty() => files.chunks(size).collect(),
+        _ => vec![&files[..]],
+    };
+    let total_pages = pages.len().max(1);
+
+    for (i, page_files) in pages.into_iter().enumerate() {
+        let page = i + 1;
+
+        let pager = page_size.map(|size| Pager {
+            current: page,
+            total_pages,
+            page_size: size,
+            prev: (page > 1).then(|| paged_name(&opts.name, page - 1)),
+            next: (page < total_pages).then(|| paged_name(&opts.name, page + 1)),
+        });
 
         let html = tera.render(
             "index.html",
             &Context::from_serialize(Product {
                 ig: Index {
-                    root: base.clone()
-                        + k.strip_prefix('.')
-                            .unwrap_or("")
-                            .strip_prefix('/')
-                            .unwrap_or(""),
-                    files,
+                    root: root.clone(),
+                    files: page_files.to_vec(),
+                    sort: opts
+                        .sort
+                        .map(|(key, dir)| format!("{}:{}", key.as_str(), dir.as_str())),
+                    pager,
                     generator: Generator {
                         name: env!("CARGO_PKG_NAME").into(),
                         version: env!("CARGO_PKG_VERSION").into(),
@@ -237,17 +691,210 @@ fn generate(
             })?,
         )?;
 
-        if if_print {
+        if opts.print {
             println!("{}", html)
         }
 
-        let mut file = File::create(k.to_owned() + MAIN_SEPARATOR_STR + &name)?;
+        let filename = paged_name(&opts.name, page);
+        let mut file = File::create(k.to_owned() + MAIN_SEPARATOR_STR + &filename)?;
         file.write_all(html.as_bytes())?;
     }
 
     Ok(())
 }
 
+/// Derive a page's output filename from the configured `name`: page 1 keeps
+/// `name` unchanged (e.g. `index.html`), later pages get `-N` spliced in
+/// before the extension (e.g. `index-2.html`).
+fn paged_name(name: &str, page: usize) -> String {
+    if page == 1 {
+        return name.to_owned();
+    }
+
+    match name.rsplit_once('.') {
+        Some((stem, ext)) => format!("{}-{}.{}", stem, page, ext),
+        None => format!("{}-{}", name, page),
+    }
+}
+
+/// Whether `name` is one of `rindgen`'s own generated output files for this
+/// run: the index page (any page, see [`paged_name`]), `feed.xml` when
+/// `--feed` is set, or a `*.preview.html` when `--previews` is set. Used to
+/// keep the generator from listing its own output as content
+/// (`collect_entries`) and from rebuilding in a loop over its own writes
+/// (`serve::watch_and_serve`).
+pub(crate) fn is_generated_output(name: &str, opts: &GenerateOptions) -> bool {
+    if name == opts.name {
+        return true;
+    }
+    if opts.page_size.is_some() && is_paged_name(name, &opts.name) {
+        return true;
+    }
+    if opts.feed.is_some() && name == "feed.xml" {
+        return true;
+    }
+    if opts.previews && name.ends_with(".preview.html") {
+        return true;
+    }
+
+    false
+}
+
+/// Whether `name` matches [`paged_name`]`(base, N)` for some page `N > 1`.
+fn is_paged_name(name: &str, base: &str) -> bool {
+    let (stem, ext) = base.rsplit_once('.').unwrap_or((base, ""));
+
+    let Some(rest) = name.strip_prefix(stem).and_then(|r| r.strip_prefix('-')) else {
+        return false;
+    };
+
+    let number = if ext.is_empty() {
+        rest
+    } else {
+        match rest.strip_suffix(ext).and_then(|r| r.strip_suffix('.')) {
+            Some(number) => number,
+            None => return false,
+        }
+    };
+
+    number
+        .parse::<usize>()
+        .is_ok_and(|n| n > 1 && n.to_string() == number)
+}
+
+/// Write `feed.xml` for a single directory: its files sorted newest-first
+/// by modification time, capped at `opts.feed_limit`.
+fn render_feed(
+    k: &str,
+    root: &str,
+    files: &[FileItem],
+    tera: &Tera,
+    opts: &GenerateOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(kind) = opts.feed else {
+        return Ok(());
+    };
+
+    let mut entries: Vec<&FileItem> = files.iter().filter(|f| !f.is_dir).collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.modified_at));
+    if let Some(limit) = opts.feed_limit {
+        entries.truncate(limit);
+    }
+
+    let feed_entries = entries
+        .iter()
+        .map(|f| FeedEntry {
+            title: f.name.clone(),
+            link: join_url(root, &f.name),
+            updated: match kind {
+                FeedKind::Atom => f.modified_at.to_rfc3339(),
+                FeedKind::Rss => f.modified_at.to_rfc2822(),
+            },
+            mime: f.mime.clone(),
+        })
+        .collect();
+
+    let xml = tera.render(
+        "feed.xml",
+        &Context::from_serialize(FeedProduct {
+            feed: Feed {
+                kind: kind.as_str().into(),
+                root: root.to_owned(),
+                generator: Generator {
+                    name: env!("CARGO_PKG_NAME").into(),
+                    version: env!("CARGO_PKG_VERSION").into(),
+                    url: env!("CARGO_PKG_HOMEPAGE").into(),
+                },
+                entries: feed_entries,
+            },
+        })?,
+    )?;
+
+    let mut file = File::create(k.to_owned() + MAIN_SEPARATOR_STR + "feed.xml")?;
+    file.write_all(xml.as_bytes())?;
+
+    Ok(())
+}
+
+/// Render and write a syntax-highlighted preview page for `f`, returning the
+/// preview's filename (relative to `k`) on success. Returns `None` (rather
+/// than a hard error) for anything that isn't renderable as UTF-8 text, or
+/// if the configured `--highlight-theme` is unknown, so a handful of
+/// unreadable files don't abort the whole run.
+fn render_preview(
+    k: &str,
+    f: &DirEntry,
+    root: &str,
+    highlighter: &preview::Highlighter,
+    tera: &Tera,
+    opts: &GenerateOptions,
+) -> Option<String> {
+    let contents = std::fs::read_to_string(f.path()).ok()?;
+    let extension = f.path().extension()?.to_str()?;
+    let html = highlighter.highlight(extension, &opts.highlight_theme, &contents)?;
+
+    let name = f.file_name().to_str()?.to_owned();
+    let filename = name.clone() + ".preview.html";
+
+    let rendered = tera
+        .render(
+            "preview.html",
+            &Context::from_serialize(PreviewProduct {
+                preview: Preview {
+                    name,
+                    root: root.to_owned(),
+                    html,
+                    generator: Generator {
+                        name: env!("CARGO_PKG_NAME").into(),
+                        version: env!("CARGO_PKG_VERSION").into(),
+                        url: env!("CARGO_PKG_HOMEPAGE").into(),
+                    },
+                },
+            })
+            .ok()?,
+        )
+        .ok()?;
+
+    let mut file = File::create(k.to_owned() + MAIN_SEPARATOR_STR + &filename).ok()?;
+    file.write_all(rendered.as_bytes()).ok()?;
+
+    Some(filename)
+}
+
+/// Join a directory's absolute `root` URL with an entry's file name.
+fn join_url(root: &str, name: &str) -> String {
+    if root.ends_with('/') {
+        format!("{}{}", root, name)
+    } else {
+        format!("{}/{}", root, name)
+    }
+}
+
+/// Sort `files` by the requested key/direction, then (if `group_dirs`)
+/// stably partition directories above files so each partition keeps the
+/// ordering just applied.
+fn sort_files(files: &mut [FileItem], sort: Option<(SortKey, SortDir)>, group_dirs: bool) {
+    if let Some((key, dir)) = sort {
+        files.sort_by(|a, b| {
+            let ord = match key {
+                SortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                SortKey::Size => a.size_bytes.cmp(&b.size_bytes),
+                SortKey::Modified => a.modified_at.cmp(&b.modified_at),
+                SortKey::Type => a.mime.cmp(&b.mime),
+            };
+            if dir == SortDir::Desc {
+                ord.reverse()
+            } else {
+                ord
+            }
+        });
+    }
+
+    if group_dirs {
+        files.sort_by_key(|f| !f.is_dir);
+    }
+}
+
 fn print_usage(program: &str, opts: Options) {
     let brief = format!("Usage: {} [options] PATH", program);
     print!("{}", opts.usage(&brief));
@@ -270,6 +917,10 @@ fn size_fmt(len: u64) -> String {
 }
 
 fn get_icon_by_mime(mime: String, is_dir: bool, iconset: String) -> String {
+    if let Some(theme_name) = iconset.strip_prefix("system:") {
+        return get_system_icon(&mime, is_dir, theme_name);
+    }
+
     let mut mime = mime.clone();
 
     if is_dir {
@@ -298,3 +949,33 @@ fn get_icon_by_mime(mime: String, is_dir: bool, iconset: String) -> String {
 
     "".into()
 }
+
+/// Resolve an icon from an installed freedesktop icon theme, as selected by
+/// `--iconset=system:<ThemeName>`.
+fn get_system_icon(mime: &str, is_dir: bool, theme_name: &str) -> String {
+    let icon_names = icon_name_candidates(mime, is_dir);
+
+    match icons::resolve(&icon_names, theme_name, 48) {
+        Some(bytes) => BASE64_STANDARD.encode(bytes),
+        None => "".into(),
+    }
+}
+
+/// MIME type to freedesktop icon name fallback chain, e.g. `text/plain` ->
+/// `text-plain` -> `text-x-generic` -> `unknown`.
+fn icon_name_candidates(mime: &str, is_dir: bool) -> Vec<String> {
+    if is_dir {
+        return vec!["inode-directory".into()];
+    }
+
+    let segments: Vec<&str> = mime.splitn(2, '/').collect();
+    if segments.len() < 2 {
+        return vec!["unknown".into()];
+    }
+
+    vec![
+        format!("{}-{}", segments[0], segments[1]),
+        format!("{}-x-generic", segments[0]),
+        "unknown".into(),
+    ]
+}
@@ -0,0 +1,53 @@
+//! Syntax-highlighted preview pages for text-like files, rendered with
+//! `syntect` and linked from each `FileItem` via `--previews`.
+
+use syntect::highlighting::ThemeSet;
+use syntect::html::highlighted_html_for_string;
+use syntect::parsing::SyntaxSet;
+
+/// Extensions treated as previewable even when `mime_guess` doesn't
+/// recognize them as `text/*` (e.g. source files such as `.rs` or `.toml`).
+const CODE_EXTENSIONS: &[&str] = &[
+    "rs", "toml", "json", "yaml", "yml", "js", "ts", "py", "go", "c", "h", "cpp", "hpp", "java",
+    "rb", "sh", "css", "md", "sql",
+];
+
+/// Loads syntect's bundled syntaxes/themes once and reuses them across
+/// every preview rendered in a `generate` run.
+pub struct Highlighter {
+    syntax_set: SyntaxSet,
+    theme_set: ThemeSet,
+}
+
+impl Highlighter {
+    pub fn new() -> Self {
+        Self {
+            syntax_set: SyntaxSet::load_defaults_newlines(),
+            theme_set: ThemeSet::load_defaults(),
+        }
+    }
+
+    /// Render `contents` to a highlighted HTML fragment, picking the syntax
+    /// by `extension` (falling back to plain text) and `theme` by name.
+    /// Returns `None` if `theme` isn't a known syntect theme.
+    pub fn highlight(&self, extension: &str, theme: &str, contents: &str) -> Option<String> {
+        let syntax = self
+            .syntax_set
+            .find_syntax_by_extension(extension)
+            .unwrap_or_else(|| self.syntax_set.find_syntax_plain_text());
+        let theme = self.theme_set.themes.get(theme)?;
+
+        highlighted_html_for_string(contents, &self.syntax_set, syntax, theme).ok()
+    }
+}
+
+/// Whether `path`/`mime` looks like a text file worth previewing.
+pub fn is_previewable(mime: &str, path: &std::path::Path) -> bool {
+    if mime.starts_with("text/") {
+        return true;
+    }
+
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| CODE_EXTENSIONS.contains(&ext))
+}
@@ -0,0 +1,220 @@
+//! Watch-and-serve mode: keeps generated `index.html` files in sync with the
+//! filesystem and serves the tree over a plain HTTP listener.
+
+use crate::GenerateOptions;
+use notify::event::{CreateKind, RemoveKind};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+const DEBOUNCE: Duration = Duration::from_millis(300);
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Watch `.` (the directory `generate` already chdir'd into) for changes,
+/// re-rendering only the affected directories, while serving the tree over
+/// `127.0.0.1:port`.
+pub fn watch_and_serve(opts: &GenerateOptions, port: u16) -> Result<(), Box<dyn std::error::Error>> {
+    let cwd = std::env::current_dir()?;
+    let (tx, rx) = channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    watcher.watch(Path::new("."), RecursiveMode::Recursive)?;
+
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    listener.set_nonblocking(true)?;
+    println!("Serving on http://127.0.0.1:{} (watching for changes)", port);
+
+    let mut full_rebuild = false;
+    let mut dirty_dirs: HashSet<String> = HashSet::new();
+    let mut last_event = Instant::now();
+
+    loop {
+        match rx.recv_timeout(Duration::from_millis(50)) {
+            Ok(Ok(event)) => {
+                // A directory being created or removed changes the shape of
+                // the whole tree, so fall back to a full rebuild; anything
+                // else (file content changes, file create/remove) only
+                // needs the directory it lives in re-rendered.
+                let is_structural = matches!(
+                    event.kind,
+                    EventKind::Create(CreateKind::Folder) | EventKind::Remove(RemoveKind::Folder)
+                );
+
+                for p in &event.paths {
+                    // Ignore our own writes (index pages, feed.xml,
+                    // previews, ...) so we don't rebuild in a loop.
+                    let is_self_write = p
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| crate::is_generated_output(n, opts));
+                    if is_self_write {
+                        continue;
+                    }
+
+                    last_event = Instant::now();
+                    if is_structural {
+                        full_rebuild = true;
+                        continue;
+                    }
+
+                    match relative_parent_key(p, &cwd) {
+                        Some(key) => {
+                            dirty_dirs.insert(key);
+                        }
+                        // Couldn't place the event under a known directory
+                        // (e.g. a symlinked watch root); don't risk leaving
+                        // a directory stale.
+                        None => full_rebuild = true,
+                    }
+                }
+            }
+            Ok(Err(e)) => eprintln!("watch error: {}", e),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        if (full_rebuild || !dirty_dirs.is_empty()) && last_event.elapsed() >= DEBOUNCE {
+            let result = if full_rebuild {
+                rebuild(opts)
+            } else {
+                rebuild_dirs(&dirty_dirs, opts)
+            };
+            if let Err(e) = result {
+                eprintln!("regenerate failed: {}", e);
+            }
+            full_rebuild = false;
+            dirty_dirs.clear();
+        }
+
+        if let Ok((stream, _)) = listener.accept() {
+            handle_conn(stream, &opts.name);
+        }
+    }
+
+    Ok(())
+}
+
+/// Map an absolute event path to the `collect_entries`-style key (e.g. `.`
+/// or `./subdir`) of the directory it lives in, so `rebuild_dirs` can find
+/// that directory's fresh entries in a newly-collected map. Returns `None`
+/// if `path` isn't under `cwd` (the watched root).
+fn relative_parent_key(path: &Path, cwd: &Path) -> Option<String> {
+    let rel = path.strip_prefix(cwd).ok()?;
+    let parent = rel.parent()?;
+
+    if parent.as_os_str().is_empty() {
+        Some(".".to_string())
+    } else {
+        Some(format!(".{}{}", std::path::MAIN_SEPARATOR, parent.display()))
+    }
+}
+
+/// Re-collect every directory's entries and re-render all of them.
+///
+/// A directory being created or removed changes the shape of the whole
+/// tree, so rather than trying to diff the old and new `map` we simply
+/// recompute it and re-render; `render_directory` is cheap relative to the
+/// filesystem events it's reacting to.
+fn rebuild(opts: &GenerateOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let map = crate::collect_entries(opts)?;
+    let tera = crate::build_tera(opts)?;
+    crate::render_tree(&map, &tera, opts)
+}
+
+/// Re-render only `dirs` (each a `collect_entries`-style directory key),
+/// using a freshly-collected map so file stats are current. The walk itself
+/// is read-only, so re-running it doesn't trigger another rebuild; only the
+/// `render_directory` calls below write anything.
+fn rebuild_dirs(
+    dirs: &HashSet<String>,
+    opts: &GenerateOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let map = crate::collect_entries(opts)?;
+    let tera = crate::build_tera(opts)?;
+    let highlighter = opts.previews.then(crate::preview::Highlighter::new);
+
+    for key in dirs {
+        // A directory that lost its last entry (e.g. the only file in it
+        // was deleted) has no key in a freshly-collected `map` at all, since
+        // `collect_entries` only creates entries via `push`. Render it with
+        // an empty list rather than skipping it, or its `index.html` would
+        // keep listing files that no longer exist.
+        let empty = Vec::new();
+        let entries = map.get(key).unwrap_or(&empty);
+        crate::render_directory(key, entries, &tera, opts, highlighter.as_ref())?;
+    }
+
+    Ok(())
+}
+
+fn handle_conn(mut stream: TcpStream, index_name: &str) {
+    // `listener` is non-blocking but `accept()` hands back a stream that
+    // defaults to blocking with no deadline; without this, a client that's
+    // slow to send its request line (or just holds the socket open) would
+    // wedge this whole function, and with it the watcher/rebuild loop that
+    // calls it synchronously.
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+
+    let mut buf = [0u8; 4096];
+    let n = match stream.read(&mut buf) {
+        Ok(n) if n > 0 => n,
+        _ => return,
+    };
+    let request = String::from_utf8_lossy(&buf[..n]);
+    let requested = request
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .unwrap_or("/");
+
+    let mut fs_path = requested.trim_start_matches('/').to_string();
+    if fs_path.is_empty() || fs_path.ends_with('/') {
+        fs_path.push_str(index_name);
+    }
+
+    if !is_safe_path(&fs_path) {
+        let _ = write_response(&mut stream, "400 Bad Request", "text/plain", b"400 Bad Request");
+        return;
+    }
+
+    match std::fs::read(&fs_path) {
+        Ok(body) => {
+            let mime = mime_guess::from_path(&fs_path).first_or_octet_stream();
+            let _ = write_response(&mut stream, "200 OK", mime.as_ref(), &body);
+        }
+        Err(_) => {
+            let _ = write_response(&mut stream, "404 Not Found", "text/plain", b"404 Not Found");
+        }
+    }
+}
+
+/// Reject any request path with a component that could escape the served
+/// root (`..`, a bare drive/root prefix), rather than joining it onto the
+/// served directory and letting `std::fs::read` resolve wherever that
+/// lands — e.g. `GET /../../../../etc/passwd`.
+fn is_safe_path(path: &str) -> bool {
+    use std::path::Component;
+
+    Path::new(path)
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+}
+
+fn write_response(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+) -> std::io::Result<()> {
+    let header = format!(
+        "HTTP/1.1 {}\r\nContent-Length: {}\r\nContent-Type: {}\r\nConnection: close\r\n\r\n",
+        status,
+        body.len(),
+        content_type
+    );
+    stream.write_all(header.as_bytes())?;
+    stream.write_all(body)
+}